@@ -0,0 +1,225 @@
+use std::convert::TryFrom;
+
+use ebml_iterable::tools as ebml_tools;
+use ebml_iterable::tags::TagData;
+
+use crate::matroska_spec::SimpleBlock;
+
+const CLUSTER_ID: u64 = 0x1F43B675;
+const TIMECODE_ID: u64 = 0xE7;
+const SIMPLE_BLOCK_ID: u64 = 0xA3;
+const UNKNOWN_SIZE: u8 = 0xFF;
+
+///
+/// An iterator adapter that buffers a stream of `(tag id, tag)` pairs and emits self-contained WebM
+/// chunks, each starting at a video keyframe with its own Cluster header, suitable for low-latency HTTP
+/// streaming.
+///
+/// Every tag seen before the first keyframe - other than the source stream's own `Cluster`/`Timecode`
+/// framing, which this adapter strips and resynthesizes itself - is treated as header content (the EBML
+/// head, Segment and Tracks elements) and is repeated verbatim at the start of every emitted chunk. Any
+/// block seen before the first keyframe is dropped, since a chunk can't be independently decodable without
+/// one. Each chunk then opens with a freshly synthesized Cluster (using an unknown size, as is standard for
+/// a live-streamed Cluster) whose `Timecode` is the keyframe's original absolute timestamp, computed from
+/// the source stream's last-seen `Timecode` plus the keyframe's own cluster-relative value; the keyframe's
+/// own relative timecode is rewritten to `0` since it is now the first block of that cluster.
+///
+pub struct WebmChunker<I: Iterator<Item = (u64, TagData)>> {
+    source: I,
+    header: Vec<TagData>,
+    header_captured: bool,
+    pending: Option<SimpleBlock>,
+    source_cluster_timecode: u64,
+}
+
+impl<I: Iterator<Item = (u64, TagData)>> WebmChunker<I> {
+    pub fn new(source: I) -> Self {
+        WebmChunker {
+            source,
+            header: Vec::new(),
+            header_captured: false,
+            pending: None,
+            source_cluster_timecode: 0,
+        }
+    }
+}
+
+fn build_cluster_header(timecode: i64) -> TagData {
+    let mut timecode_child = Vec::new();
+    timecode_child.extend_from_slice(&ebml_tools::write_tag_id(TIMECODE_ID));
+    timecode_child.extend_from_slice(&ebml_tools::write_vint(8).expect("8 always fits in a vint size"));
+    timecode_child.extend_from_slice(&(timecode as u64).to_be_bytes());
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&ebml_tools::write_tag_id(CLUSTER_ID));
+    data.push(UNKNOWN_SIZE);
+    data.extend_from_slice(&timecode_child);
+
+    TagData::Binary(data)
+}
+
+///
+/// What an incoming `(id, tag)` pair means to the chunker: a new block to (maybe) emit, an update to the
+/// source stream's running Cluster `Timecode` (consumed, never forwarded - the chunker synthesizes its
+/// own Cluster/Timecode framing per chunk), or an opaque tag to pass through untouched.
+///
+enum ClassifiedTag {
+    Block(SimpleBlock),
+    SourceTimecode(u64),
+    Dropped,
+    Other(TagData),
+}
+
+fn classify(id: u64, tag: TagData) -> ClassifiedTag {
+    match id {
+        SIMPLE_BLOCK_ID => match SimpleBlock::try_from(tag) {
+            Ok(block) => ClassifiedTag::Block(block),
+            Err(_) => ClassifiedTag::Dropped,
+        },
+        TIMECODE_ID => match tag {
+            TagData::UnsignedInt(value) => ClassifiedTag::SourceTimecode(value),
+            _ => ClassifiedTag::Dropped,
+        },
+        CLUSTER_ID => ClassifiedTag::Dropped, // the chunker synthesizes its own Cluster per chunk
+        _ => ClassifiedTag::Other(tag),
+    }
+}
+
+impl<I: Iterator<Item = (u64, TagData)>> Iterator for WebmChunker<I> {
+    type Item = Vec<TagData>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.header_captured {
+            loop {
+                match self.source.next() {
+                    Some((id, tag)) => match classify(id, tag) {
+                        ClassifiedTag::Block(block) => {
+                            if !block.keyframe {
+                                // can't start an independently-decodable chunk without a keyframe to seek to
+                                continue;
+                            }
+                            self.header_captured = true;
+                            self.pending = Some(block);
+                            break;
+                        },
+                        ClassifiedTag::SourceTimecode(value) => self.source_cluster_timecode = value,
+                        ClassifiedTag::Dropped => {},
+                        ClassifiedTag::Other(tag) => self.header.push(tag),
+                    },
+                    None => return None,
+                }
+            }
+        }
+
+        let mut keyframe = self.pending.take()?;
+        let cluster_timecode = keyframe.block.absolute_timestamp_ticks(self.source_cluster_timecode);
+        keyframe.block.value = 0;
+
+        let mut chunk = self.header.clone();
+        chunk.push(build_cluster_header(cluster_timecode));
+        chunk.push(keyframe.into());
+
+        loop {
+            match self.source.next() {
+                Some((id, tag)) => match classify(id, tag) {
+                    ClassifiedTag::Block(block) => {
+                        if block.keyframe {
+                            self.pending = Some(block);
+                            break;
+                        }
+
+                        chunk.push(block.into());
+                    },
+                    ClassifiedTag::SourceTimecode(value) => self.source_cluster_timecode = value,
+                    ClassifiedTag::Dropped => {},
+                    ClassifiedTag::Other(tag) => chunk.push(tag),
+                },
+                None => break,
+            }
+        }
+
+        Some(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebmChunker;
+    use super::TagData;
+    use super::{CLUSTER_ID, TIMECODE_ID, SIMPLE_BLOCK_ID};
+
+    const HEADER_ID: u64 = 0x1549A966; // placeholder id for a non-framing, non-block "header" tag
+
+    fn block(timecode: u8, flags: u8, payload: u8) -> (u64, TagData) {
+        (SIMPLE_BLOCK_ID, TagData::Binary(vec![0x81, 0x00, timecode, flags, payload]))
+    }
+
+    fn timecode(value: u64) -> (u64, TagData) {
+        (TIMECODE_ID, TagData::UnsignedInt(value))
+    }
+
+    fn cluster() -> (u64, TagData) {
+        (CLUSTER_ID, TagData::Binary(Vec::new()))
+    }
+
+    #[test]
+    fn drops_leading_delta_frames_and_starts_each_chunk_with_a_cluster() {
+        let tags = vec![
+            (HEADER_ID, TagData::UnsignedInt(1)), // header tag
+            cluster(),
+            timecode(0),
+            block(0x07, 0x00, 0xff), // delta frame before any keyframe - must be dropped
+            block(0x00, 0x80, 0xaa), // keyframe
+            block(0x01, 0x00, 0xbb), // delta frame
+            cluster(),
+            timecode(1000),
+            block(0x02, 0x80, 0xcc), // keyframe
+            block(0x03, 0x00, 0xdd), // delta frame
+        ];
+
+        let mut chunker = WebmChunker::new(tags.into_iter());
+
+        let first_chunk = chunker.next().unwrap();
+        assert_eq!(4, first_chunk.len());
+        assert_eq!(TagData::UnsignedInt(1), first_chunk[0]);
+
+        match &first_chunk[1] {
+            TagData::Binary(data) => {
+                assert_eq!(&super::ebml_tools::write_tag_id(CLUSTER_ID)[..], &data[0..4]);
+                assert_eq!(&super::ebml_tools::write_tag_id(TIMECODE_ID)[..], &data[5..6]);
+            },
+            _ => panic!("expected a binary Cluster tag"),
+        }
+
+        let second_chunk = chunker.next().unwrap();
+        assert_eq!(4, second_chunk.len());
+        assert_eq!(TagData::UnsignedInt(1), second_chunk[0]);
+
+        match &second_chunk[1] {
+            TagData::Binary(data) => {
+                // keyframe at relative value 2 within a source cluster whose Timecode is 1000
+                assert_eq!(&1002u64.to_be_bytes()[..], &data[7..15]);
+            },
+            _ => panic!("expected a binary Cluster tag"),
+        }
+
+        assert!(chunker.next().is_none());
+    }
+
+    #[test]
+    fn absolute_timecode_survives_a_source_cluster_boundary() {
+        let tags = vec![
+            cluster(),
+            timecode(1000),
+            block(0x05, 0x80, 0xaa), // keyframe at relative value 5 within source cluster tc=1000
+        ];
+
+        let mut chunker = WebmChunker::new(tags.into_iter());
+        let chunk = chunker.next().unwrap();
+
+        match &chunk[0] {
+            TagData::Binary(data) => assert_eq!(&1005u64.to_be_bytes()[..], &data[7..15]),
+            _ => panic!("expected a binary Cluster tag"),
+        }
+    }
+}
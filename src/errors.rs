@@ -0,0 +1,25 @@
+use std::fmt;
+
+///
+/// Errors that can occur when coercing [`TagData`](ebml_iterable::tags::TagData) values into the typed Matroska elements exposed by this crate, or when operating the crate's [`ogg_remux`](crate::ogg_remux) feature.
+///
+#[derive(Debug)]
+pub enum WebmError {
+    SimpleBlockCoercionError(String),
+    BlockCoercionError(String),
+    TrackEntryCoercionError(String),
+    OggRemuxError(String),
+}
+
+impl fmt::Display for WebmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebmError::SimpleBlockCoercionError(message) => write!(f, "{}", message),
+            WebmError::BlockCoercionError(message) => write!(f, "{}", message),
+            WebmError::TrackEntryCoercionError(message) => write!(f, "{}", message),
+            WebmError::OggRemuxError(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for WebmError {}
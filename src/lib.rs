@@ -0,0 +1,6 @@
+pub mod chunker;
+pub mod errors;
+pub mod matroska_spec;
+#[cfg(feature = "ogg")]
+pub mod ogg_remux;
+pub mod timecode_fixer;
@@ -0,0 +1,263 @@
+use std::convert::{TryInto, TryFrom};
+
+use ebml_iterable::tools as ebml_tools;
+use ebml_iterable::tags::TagData;
+
+use super::super::errors::WebmError;
+
+///
+/// Describes how multiple frames were packed ("laced") into a single [`Block`]/[`SimpleBlock`](super::SimpleBlock) payload.
+///
+/// See the [Matroska lacing spec](https://www.matroska.org/technical/basics.html#block-structure) for details on each strategy.
+///
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum BlockLacing {
+    Xiph,
+    FixedSize,
+    EBML,
+}
+
+///
+/// A typed interpretation of the Matroska "Block" element.
+///
+/// This struct has fields specific to the [Block](https://www.matroska.org/technical/basics.html#block-structure) element as defined by the [Matroska Spec](http://www.matroska.org/technical/specs/index.html).  This struct implements `TryFrom<TagData>` and `Into<TagData>` to simplify coercion to and from regular [`TagData::Binary`] values.
+///
+pub struct Block {
+    pub track: u64,
+    pub value: i16,
+    pub invisible: bool,
+    pub lacing: Option<BlockLacing>,
+    data: Vec<u8>,
+}
+
+impl TryFrom<TagData> for Block {
+    type Error = WebmError;
+
+    fn try_from(value: TagData) -> Result<Self, Self::Error> {
+        if let TagData::Binary(data) = &value {
+            let data = data;
+            let mut position: usize = 0;
+            let (track, track_size) = ebml_tools::read_vint(data)
+                .map_err(|_| WebmError::BlockCoercionError(String::from("Unable to read track data in Block.")))?
+                .ok_or_else(|| WebmError::BlockCoercionError(String::from("Unable to read track data in Block.")))?;
+
+            position += track_size;
+            let value_bytes: [u8; 2] = data[position..position + 2]
+                .try_into()
+                .map_err(|_| WebmError::BlockCoercionError(String::from("Unable to read timecode in Block.")))?;
+            let timecode = i16::from_be_bytes(value_bytes);
+            position += 2;
+
+            let flags = data[position];
+            let invisible = flags & 0x08 == 0x08;
+            let lacing = match flags & 0x06 {
+                0x02 => Some(BlockLacing::Xiph),
+                0x04 => Some(BlockLacing::FixedSize),
+                0x06 => Some(BlockLacing::EBML),
+                _ => None,
+            };
+
+            Ok(Block {
+                track,
+                value: timecode,
+                invisible,
+                lacing,
+                data: data.clone(),
+            })
+        } else {
+            Err(WebmError::BlockCoercionError(String::from("Expected binary tag type for Block tag, but received a different type!")))
+        }
+    }
+}
+
+#[allow(clippy::from_over_into)]
+impl Into<TagData> for Block {
+    fn into(self) -> TagData {
+        let mut data = self.data;
+        let mut position: usize = 0;
+        let (_track, track_size) = ebml_tools::read_vint(&data)
+            .expect("Invalid data passed to block.  Could not read track.")
+            .expect("Invalid data passed to block.  Could not read track.");
+
+        position += track_size;
+        data[position..position + 2].copy_from_slice(&self.value.to_be_bytes());
+        position += 2;
+
+        let flags = &mut data[position];
+
+        *flags &= !0x08;
+        if self.invisible {
+            *flags |= 0x08;
+        }
+
+        *flags &= !0x06;
+        match self.lacing {
+            Some(BlockLacing::Xiph) => *flags |= 0x02,
+            Some(BlockLacing::FixedSize) => *flags |= 0x04,
+            Some(BlockLacing::EBML) => *flags |= 0x06,
+            None => {},
+        }
+
+        TagData::Binary(data)
+    }
+}
+
+///
+/// Reads an EBML-lacing size delta.  These are encoded the same way as a regular vint, but the
+/// decoded value is range-shifted so it can represent a signed delta from the previous frame size.
+///
+fn read_signed_vint(data: &[u8]) -> Result<(i64, usize), WebmError> {
+    let (value, size) = ebml_tools::read_vint(data)
+        .map_err(|_| WebmError::BlockCoercionError(String::from("Unable to read lacing size in Block.")))?
+        .ok_or_else(|| WebmError::BlockCoercionError(String::from("Unable to read lacing size in Block.")))?;
+
+    let bias = (1i64 << (7 * size - 1)) - 1;
+    Ok((value as i64 - bias, size))
+}
+
+impl Block {
+    ///
+    /// Computes this block's absolute timestamp in nanoseconds, given the `Timecode` of the enclosing Cluster and the segment's `TimestampScale`.
+    ///
+    /// `Block`/`SimpleBlock` only carry a 16-bit timecode relative to their enclosing cluster - this resolves that into a real timestamp.
+    ///
+    pub fn absolute_timestamp(&self, cluster_timecode: u64, timestamp_scale: u64) -> i64 {
+        self.absolute_timestamp_ticks(cluster_timecode) * timestamp_scale as i64
+    }
+
+    ///
+    /// Computes this block's absolute timestamp in the segment's native tick units (i.e. before scaling by `TimestampScale`), given the `Timecode` of the enclosing Cluster.
+    ///
+    pub fn absolute_timestamp_ticks(&self, cluster_timecode: u64) -> i64 {
+        cluster_timecode as i64 + self.value as i64
+    }
+
+    ///
+    /// Splits this block's payload into the individual coded frames described by its [`lacing`](Block::lacing) value.
+    ///
+    /// When [`lacing`](Block::lacing) is `None`, the entire remaining payload is returned as a single frame.
+    ///
+    pub fn read_frames(&self) -> Result<Vec<&[u8]>, WebmError> {
+        let data = &self.data;
+        let mut position: usize = 0;
+        let (_track, track_size) = ebml_tools::read_vint(data)
+            .map_err(|_| WebmError::BlockCoercionError(String::from("Unable to read track data in Block.")))?
+            .ok_or_else(|| WebmError::BlockCoercionError(String::from("Unable to read track data in Block.")))?;
+
+        position += track_size + 2;
+        position += 1; // flags byte
+
+        let lacing = match self.lacing {
+            Some(lacing) => lacing,
+            None => return Ok(vec![&data[position..]]),
+        };
+
+        let frame_count = data[position] as usize + 1;
+        position += 1;
+
+        let mut sizes: Vec<usize> = Vec::with_capacity(frame_count - 1);
+        match lacing {
+            BlockLacing::Xiph => {
+                for _ in 0..frame_count - 1 {
+                    let mut size: usize = 0;
+                    loop {
+                        let byte = data[position];
+                        position += 1;
+                        size += byte as usize;
+                        if byte != 0xff {
+                            break;
+                        }
+                    }
+                    sizes.push(size);
+                }
+            },
+            BlockLacing::FixedSize => {
+                let frame_size = (data.len() - position) / frame_count;
+                for _ in 0..frame_count - 1 {
+                    sizes.push(frame_size);
+                }
+            },
+            BlockLacing::EBML => {
+                let (first_size, first_size_len) = ebml_tools::read_vint(&data[position..])
+                    .map_err(|_| WebmError::BlockCoercionError(String::from("Unable to read lacing size in Block.")))?
+                    .ok_or_else(|| WebmError::BlockCoercionError(String::from("Unable to read lacing size in Block.")))?;
+                position += first_size_len;
+
+                let mut previous_size = first_size as i64;
+                sizes.push(previous_size as usize);
+
+                for _ in 0..frame_count.saturating_sub(2) {
+                    let (delta, delta_len) = read_signed_vint(&data[position..])?;
+                    position += delta_len;
+                    previous_size += delta;
+                    sizes.push(previous_size as usize);
+                }
+            },
+        }
+
+        let mut frames = Vec::with_capacity(frame_count);
+        for size in sizes {
+            frames.push(&data[position..position + size]);
+            position += size;
+        }
+        frames.push(&data[position..]);
+
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::Block;
+    use super::BlockLacing;
+    use super::TagData;
+
+    #[test]
+    fn encode_preserves_mutated_value() {
+        let block_content = vec![0x81, 0x00, 0x01, 0x00, 0x01, 0x02, 0x03];
+        let mut block = Block::try_from(TagData::Binary(block_content)).unwrap();
+
+        block.value = -5;
+        let encoded: TagData = block.into();
+
+        let roundtripped = Block::try_from(encoded).unwrap();
+        assert_eq!(-5, roundtripped.value);
+    }
+
+    #[test]
+    fn absolute_timestamp_from_cluster_timecode() {
+        let block_content = vec![0x81, 0x00, 0x01, 0x00, 0x01, 0x02, 0x03];
+        let block = Block::try_from(TagData::Binary(block_content)).unwrap();
+
+        assert_eq!(1001, block.absolute_timestamp_ticks(1000));
+        assert_eq!(1_001_000, block.absolute_timestamp(1000, 1000));
+    }
+
+    #[test]
+    fn read_frames_no_lacing() {
+        let block_content = vec![0x81, 0x00, 0x01, 0x00, 0x01, 0x02, 0x03];
+        let block = Block::try_from(TagData::Binary(block_content)).unwrap();
+
+        assert_eq!(None, block.lacing);
+
+        let frames = block.read_frames().unwrap();
+        assert_eq!(1, frames.len());
+        assert_eq!(&[0x01, 0x02, 0x03], frames[0]);
+    }
+
+    #[test]
+    fn read_frames_fixed_size_lacing() {
+        // track 1, timecode 0, flags with fixed-size lacing, 2 frames (count - 1 = 1), 6 bytes of payload
+        let block_content = vec![0x81, 0x00, 0x00, 0x04, 0x01, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+        let block = Block::try_from(TagData::Binary(block_content)).unwrap();
+
+        assert_eq!(Some(BlockLacing::FixedSize), block.lacing);
+
+        let frames = block.read_frames().unwrap();
+        assert_eq!(2, frames.len());
+        assert_eq!(&[0x01, 0x02, 0x03], frames[0]);
+        assert_eq!(&[0x04, 0x05, 0x06], frames[1]);
+    }
+}
@@ -0,0 +1,7 @@
+mod block;
+mod simple_block;
+mod track_entry;
+
+pub use block::{Block, BlockLacing};
+pub use simple_block::SimpleBlock;
+pub use track_entry::{TrackEntry, AudioSettings, VideoSettings};
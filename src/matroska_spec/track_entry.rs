@@ -0,0 +1,290 @@
+use std::convert::{TryFrom, TryInto};
+
+use ebml_iterable::tools as ebml_tools;
+use ebml_iterable::tags::TagData;
+
+use super::super::errors::WebmError;
+
+const TRACK_NUMBER_ID: u64 = 0xD7;
+const TRACK_UID_ID: u64 = 0x73C5;
+const CODEC_ID_ID: u64 = 0x86;
+const CODEC_PRIVATE_ID: u64 = 0x63A2;
+const LANGUAGE_ID: u64 = 0x22B59C;
+const DEFAULT_DURATION_ID: u64 = 0x23E383;
+const AUDIO_ID: u64 = 0xE1;
+const VIDEO_ID: u64 = 0xE0;
+const SAMPLING_FREQUENCY_ID: u64 = 0xB5;
+const CHANNELS_ID: u64 = 0x9F;
+const PIXEL_WIDTH_ID: u64 = 0xB0;
+const PIXEL_HEIGHT_ID: u64 = 0xBA;
+
+///
+/// Typed audio settings parsed from a TrackEntry's `Audio` child element.
+///
+pub struct AudioSettings {
+    pub sampling_frequency: f64,
+    pub channels: u64,
+}
+
+///
+/// Typed video settings parsed from a TrackEntry's `Video` child element.
+///
+pub struct VideoSettings {
+    pub pixel_width: u64,
+    pub pixel_height: u64,
+}
+
+///
+/// A typed interpretation of the Matroska "TrackEntry" element.
+///
+/// This struct has fields specific to the [TrackEntry](https://www.matroska.org/technical/elements.html) element as defined by the [Matroska Spec](http://www.matroska.org/technical/specs/index.html).  This struct implements `TryFrom<TagData>` and `Into<TagData>` to simplify coercion to and from regular [`TagData::Binary`] values, the same way [`SimpleBlock`](super::SimpleBlock) does for the Block/SimpleBlock elements.  It lets callers map a block's [`track`](super::Block::track) number to its codec without hand-parsing the Tracks container.
+///
+pub struct TrackEntry {
+    pub number: u64,
+    pub uid: u64,
+    pub codec_id: String,
+    pub codec_private: Option<Vec<u8>>,
+    pub language: Option<String>,
+    pub default_duration: Option<u64>,
+    pub audio: Option<AudioSettings>,
+    pub video: Option<VideoSettings>,
+}
+
+fn read_children(data: &[u8]) -> Result<Vec<(u64, Vec<u8>)>, WebmError> {
+    let mut children = Vec::new();
+    let mut position: usize = 0;
+
+    while position < data.len() {
+        let (id, id_size) = ebml_tools::read_tag_id(&data[position..])
+            .map_err(|_| WebmError::TrackEntryCoercionError(String::from("Unable to read child tag id in TrackEntry.")))?
+            .ok_or_else(|| WebmError::TrackEntryCoercionError(String::from("Unable to read child tag id in TrackEntry.")))?;
+        position += id_size;
+
+        let (size, size_len) = ebml_tools::read_vint(&data[position..])
+            .map_err(|_| WebmError::TrackEntryCoercionError(String::from("Unable to read child tag size in TrackEntry.")))?
+            .ok_or_else(|| WebmError::TrackEntryCoercionError(String::from("Unable to read child tag size in TrackEntry.")))?;
+        position += size_len;
+
+        let size = size as usize;
+        let payload = data[position..position + size].to_vec();
+        position += size;
+
+        children.push((id, payload));
+    }
+
+    Ok(children)
+}
+
+fn read_uint(data: &[u8]) -> u64 {
+    data.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64)
+}
+
+///
+/// Reads an EBML "Float" element, which is encoded as either a 4-byte or 8-byte IEEE-754 value.
+///
+fn read_float(data: &[u8]) -> Result<f64, WebmError> {
+    match data.len() {
+        4 => {
+            let bytes: [u8; 4] = data.try_into().expect("slice of length 4");
+            Ok(f32::from_be_bytes(bytes) as f64)
+        },
+        8 => {
+            let bytes: [u8; 8] = data.try_into().expect("slice of length 8");
+            Ok(f64::from_be_bytes(bytes))
+        },
+        _ => Err(WebmError::TrackEntryCoercionError(String::from("Unexpected size for a Float element in TrackEntry."))),
+    }
+}
+
+fn read_audio(data: &[u8]) -> Result<AudioSettings, WebmError> {
+    let mut sampling_frequency = 8000.0;
+    let mut channels = 1;
+
+    for (id, payload) in read_children(data)? {
+        match id {
+            SAMPLING_FREQUENCY_ID => sampling_frequency = read_float(&payload)?,
+            CHANNELS_ID => channels = read_uint(&payload),
+            _ => {},
+        }
+    }
+
+    Ok(AudioSettings { sampling_frequency, channels })
+}
+
+fn read_video(data: &[u8]) -> Result<VideoSettings, WebmError> {
+    let mut pixel_width = 0;
+    let mut pixel_height = 0;
+
+    for (id, payload) in read_children(data)? {
+        match id {
+            PIXEL_WIDTH_ID => pixel_width = read_uint(&payload),
+            PIXEL_HEIGHT_ID => pixel_height = read_uint(&payload),
+            _ => {},
+        }
+    }
+
+    Ok(VideoSettings { pixel_width, pixel_height })
+}
+
+impl TryFrom<TagData> for TrackEntry {
+    type Error = WebmError;
+
+    fn try_from(value: TagData) -> Result<Self, Self::Error> {
+        if let TagData::Binary(data) = &value {
+            let mut number = None;
+            let mut uid = None;
+            let mut codec_id = None;
+            let mut codec_private = None;
+            let mut language = None;
+            let mut default_duration = None;
+            let mut audio = None;
+            let mut video = None;
+
+            for (id, payload) in read_children(data)? {
+                match id {
+                    TRACK_NUMBER_ID => number = Some(read_uint(&payload)),
+                    TRACK_UID_ID => uid = Some(read_uint(&payload)),
+                    CODEC_ID_ID => codec_id = Some(String::from_utf8_lossy(&payload).into_owned()),
+                    CODEC_PRIVATE_ID => codec_private = Some(payload),
+                    LANGUAGE_ID => language = Some(String::from_utf8_lossy(&payload).into_owned()),
+                    DEFAULT_DURATION_ID => default_duration = Some(read_uint(&payload)),
+                    AUDIO_ID => audio = Some(read_audio(&payload)?),
+                    VIDEO_ID => video = Some(read_video(&payload)?),
+                    _ => {},
+                }
+            }
+
+            Ok(TrackEntry {
+                number: number.ok_or_else(|| WebmError::TrackEntryCoercionError(String::from("TrackEntry is missing a TrackNumber.")))?,
+                uid: uid.ok_or_else(|| WebmError::TrackEntryCoercionError(String::from("TrackEntry is missing a TrackUID.")))?,
+                codec_id: codec_id.ok_or_else(|| WebmError::TrackEntryCoercionError(String::from("TrackEntry is missing a CodecID.")))?,
+                codec_private,
+                language,
+                default_duration,
+                audio,
+                video,
+            })
+        } else {
+            Err(WebmError::TrackEntryCoercionError(String::from("Expected binary tag type for TrackEntry tag, but received a different type!")))
+        }
+    }
+}
+
+#[allow(clippy::from_over_into)]
+impl Into<TagData> for TrackEntry {
+    fn into(self) -> TagData {
+        let mut data = Vec::new();
+
+        write_child(&mut data, TRACK_NUMBER_ID, &minimal_be_bytes(self.number));
+        write_child(&mut data, TRACK_UID_ID, &minimal_be_bytes(self.uid));
+        write_child(&mut data, CODEC_ID_ID, self.codec_id.as_bytes());
+
+        if let Some(codec_private) = &self.codec_private {
+            write_child(&mut data, CODEC_PRIVATE_ID, codec_private);
+        }
+
+        if let Some(language) = &self.language {
+            write_child(&mut data, LANGUAGE_ID, language.as_bytes());
+        }
+
+        if let Some(default_duration) = self.default_duration {
+            write_child(&mut data, DEFAULT_DURATION_ID, &minimal_be_bytes(default_duration));
+        }
+
+        if let Some(audio) = &self.audio {
+            let mut audio_data = Vec::new();
+            write_child(&mut audio_data, SAMPLING_FREQUENCY_ID, &audio.sampling_frequency.to_be_bytes());
+            write_child(&mut audio_data, CHANNELS_ID, &minimal_be_bytes(audio.channels));
+            write_child(&mut data, AUDIO_ID, &audio_data);
+        }
+
+        if let Some(video) = &self.video {
+            let mut video_data = Vec::new();
+            write_child(&mut video_data, PIXEL_WIDTH_ID, &minimal_be_bytes(video.pixel_width));
+            write_child(&mut video_data, PIXEL_HEIGHT_ID, &minimal_be_bytes(video.pixel_height));
+            write_child(&mut data, VIDEO_ID, &video_data);
+        }
+
+        TagData::Binary(data)
+    }
+}
+
+fn write_child(data: &mut Vec<u8>, id: u64, payload: &[u8]) {
+    data.extend_from_slice(&ebml_tools::write_tag_id(id));
+    data.extend_from_slice(&ebml_tools::write_vint(payload.len() as u64).expect("payload too large to encode as a vint size"));
+    data.extend_from_slice(payload);
+}
+
+///
+/// Encodes `value` as a big-endian EBML "unsigned integer" element, trimming leading zero bytes down to
+/// the minimal encoding (at least one byte, to represent zero).
+///
+fn minimal_be_bytes(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let first_nonzero = bytes.iter().position(|&byte| byte != 0).unwrap_or(bytes.len() - 1);
+    bytes[first_nonzero..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::TrackEntry;
+    use super::TagData;
+
+    #[test]
+    fn reads_4_byte_and_8_byte_sampling_frequency() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[0xD7, 0x81, 0x01]); // TrackNumber = 1
+        data.extend_from_slice(&[0x73, 0xC5, 0x81, 0x01]); // TrackUID = 1
+        data.extend_from_slice(&[0x86, 0x86, b'A', b'_', b'O', b'P', b'U', b'S']); // CodecID = "A_OPUS"
+        data.extend_from_slice(&[0xE1, 0x86]); // Audio, size 6
+        data.extend_from_slice(&[0xB5, 0x84]); // SamplingFrequency, size 4 (f32)
+        data.extend_from_slice(&48_000f32.to_be_bytes());
+
+        let track = TrackEntry::try_from(TagData::Binary(data)).unwrap();
+
+        let audio = track.audio.unwrap();
+        assert_eq!(48_000.0, audio.sampling_frequency);
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&[0xD7, 0x81, 0x01]); // TrackNumber = 1
+        data.extend_from_slice(&[0x73, 0xC5, 0x81, 0x01]); // TrackUID = 1
+        data.extend_from_slice(&[0x86, 0x86, b'A', b'_', b'O', b'P', b'U', b'S']); // CodecID = "A_OPUS"
+        data.extend_from_slice(&[0xE1, 0x8A]); // Audio, size 10
+        data.extend_from_slice(&[0xB5, 0x88]); // SamplingFrequency, size 8 (f64)
+        data.extend_from_slice(&44_100f64.to_be_bytes());
+
+        let track = TrackEntry::try_from(TagData::Binary(data)).unwrap();
+
+        let audio = track.audio.unwrap();
+        assert_eq!(44_100.0, audio.sampling_frequency);
+    }
+
+    #[test]
+    fn encode_decode_roundtrip_uses_minimal_length_integers() {
+        let track = TrackEntry {
+            number: 1,
+            uid: 1,
+            codec_id: String::from("A_OPUS"),
+            codec_private: None,
+            language: None,
+            default_duration: None,
+            audio: None,
+            video: None,
+        };
+
+        let encoded: TagData = track.into();
+        match &encoded {
+            // TrackNumber = 1, written as a single payload byte
+            TagData::Binary(data) => assert_eq!(&[0xD7, 0x81, 0x01], &data[0..3]),
+            _ => panic!("expected a binary TrackEntry tag"),
+        }
+
+        let decoded = TrackEntry::try_from(encoded).unwrap();
+
+        assert_eq!(1, decoded.number);
+        assert_eq!(1, decoded.uid);
+        assert_eq!("A_OPUS", decoded.codec_id);
+    }
+}
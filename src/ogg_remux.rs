@@ -0,0 +1,297 @@
+//! Opt-in Opus-to-Ogg remuxing, gated behind the `ogg` feature.
+//!
+//! Takes an already-selected Opus [`TrackEntry`] - callers are expected to walk their own WebM/Matroska
+//! stream and find it by codec id - and writes that track's laced frames into an Ogg container with
+//! correct granule positions, turning a downloaded WebM Opus audio stream into a playable Ogg Opus file
+//! without pulling in a full media framework.
+
+use crate::errors::WebmError;
+use crate::matroska_spec::{Block, TrackEntry};
+
+const OPUS_CODEC_ID: &str = "A_OPUS";
+const OPUS_SAMPLE_RATE: u64 = 48_000;
+// The default decoder pre-skip most Opus encoders emit; granule positions in Ogg Opus are measured from
+// the start of the stream including this pre-roll, per the Ogg Opus spec (RFC 7845 section 4.2).
+const OPUS_PRE_SKIP: u64 = 312;
+// A conservative 20ms-per-frame (48kHz) estimate, used to advance the granule position across a laced
+// block's frames without decoding each packet's TOC byte to recover its exact duration.
+const OPUS_SAMPLES_PER_FRAME: u64 = 960;
+// An Ogg page's segment table length is a single byte, so a page can carry at most 255 lacing values.
+const MAX_SEGMENTS_PER_PAGE: usize = 255;
+
+///
+/// Remuxes the Opus frames of a single, already-selected WebM audio track into an Ogg Opus bitstream.
+///
+/// This does not walk a WebM stream or pick a track itself - [`new`](OggOpusRemuxer::new) takes the
+/// caller's chosen Opus [`TrackEntry`], and [`write_block`](OggOpusRemuxer::write_block) is fed that
+/// track's [`Block`]s one at a time as the caller reads them off the source stream.  The WebM
+/// `CodecPrivate` for an Opus track is already the raw Opus identification header, so it is used verbatim
+/// as the payload of the first Ogg page.  Granule positions are derived from each block's
+/// [`Block::absolute_timestamp`] at the end of the block, scaled to the fixed 48kHz Opus clock and offset
+/// by the decoder pre-skip.
+///
+pub struct OggOpusRemuxer {
+    track: TrackEntry,
+    serial: u32,
+    sequence: u32,
+    headers_written: bool,
+}
+
+impl OggOpusRemuxer {
+    ///
+    /// Creates a remuxer for the given Opus [`TrackEntry`].  Returns `None` if the track's codec id isn't
+    /// `A_OPUS`.
+    ///
+    pub fn new(track: TrackEntry, serial: u32) -> Option<Self> {
+        if track.codec_id != OPUS_CODEC_ID {
+            return None;
+        }
+
+        Some(OggOpusRemuxer {
+            track,
+            serial,
+            sequence: 0,
+            headers_written: false,
+        })
+    }
+
+    ///
+    /// Writes the identification and comment header pages that must open an Ogg Opus stream.  Must be
+    /// called once, before any call to [`write_block`](OggOpusRemuxer::write_block).
+    ///
+    pub fn write_headers(&mut self) -> Result<Vec<u8>, WebmError> {
+        if self.headers_written {
+            return Err(WebmError::OggRemuxError(String::from("Ogg headers have already been written for this track.")));
+        }
+
+        let opus_head = self.track.codec_private.clone()
+            .ok_or_else(|| WebmError::OggRemuxError(String::from("Opus TrackEntry is missing CodecPrivate (OpusHead).")))?;
+        let opus_tags = build_opus_tags();
+
+        let mut data = Vec::new();
+        data.extend(self.write_pages(&[opus_head], 0, 0x02));
+        data.extend(self.write_pages(&[opus_tags], 0, 0x00));
+
+        self.headers_written = true;
+        Ok(data)
+    }
+
+    ///
+    /// Writes the Ogg page(s) for the laced frames in `block`, with the granule position computed at the
+    /// *end* of the block (its start timestamp plus its frames' estimated duration), offset by the Opus
+    /// decoder pre-skip, per the Ogg Opus granule position convention.
+    ///
+    pub fn write_block(&mut self, block: &Block, cluster_timecode: u64, timestamp_scale: u64) -> Result<Vec<u8>, WebmError> {
+        if !self.headers_written {
+            return Err(WebmError::OggRemuxError(String::from("Ogg headers must be written before any audio pages.")));
+        }
+
+        let frames: Vec<Vec<u8>> = block.read_frames()?
+            .into_iter()
+            .map(|frame| frame.to_vec())
+            .collect();
+
+        let start_ns = block.absolute_timestamp(cluster_timecode, timestamp_scale);
+        let start_sample = ((start_ns.max(0) as u128 * OPUS_SAMPLE_RATE as u128) / 1_000_000_000) as u64;
+        let end_sample = start_sample + frames.len() as u64 * OPUS_SAMPLES_PER_FRAME;
+        let granule_position = end_sample + OPUS_PRE_SKIP;
+
+        Ok(self.write_pages(&frames, granule_position, 0x00))
+    }
+
+    ///
+    /// Writes the final, empty Ogg page marking the end of the stream.
+    ///
+    pub fn finish(&mut self, final_granule_position: u64) -> Vec<u8> {
+        self.write_pages(&[Vec::new()], final_granule_position, 0x04)
+    }
+
+    ///
+    /// Packs `packets` into one or more Ogg pages, splitting onto a continuation page (and marking it with
+    /// the "continued packet" flag) whenever a page's segment table would otherwise overflow
+    /// [`MAX_SEGMENTS_PER_PAGE`].  Only the final page carries `granule_position`; any earlier,
+    /// mid-packet continuation page reports the "no packets finish on this page" granule of `-1`, per the
+    /// Ogg spec.  A packet whose length is an exact multiple of 255 gets an extra, terminating 0-length
+    /// segment, since a lacing value below 255 is what tells a demuxer a packet actually ends there.
+    ///
+    fn write_pages(&mut self, packets: &[Vec<u8>], granule_position: u64, header_type: u8) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut segment_table: Vec<u8> = Vec::new();
+        let mut payload: Vec<u8> = Vec::new();
+        let mut continued = false;
+
+        for packet in packets {
+            let mut offset = 0;
+            let mut last_take = 0;
+            loop {
+                if segment_table.len() >= MAX_SEGMENTS_PER_PAGE {
+                    let flags = if continued { 0x01 } else { 0x00 };
+                    output.extend(self.write_page(&segment_table, &payload, u64::MAX, flags));
+                    segment_table.clear();
+                    payload.clear();
+                    continued = true;
+                }
+
+                let take = (packet.len() - offset).min(255);
+                segment_table.push(take as u8);
+                payload.extend_from_slice(&packet[offset..offset + take]);
+                offset += take;
+                last_take = take;
+
+                if offset >= packet.len() {
+                    break;
+                }
+            }
+
+            if last_take == 255 && !packet.is_empty() {
+                if segment_table.len() >= MAX_SEGMENTS_PER_PAGE {
+                    let flags = if continued { 0x01 } else { 0x00 };
+                    output.extend(self.write_page(&segment_table, &payload, u64::MAX, flags));
+                    segment_table.clear();
+                    payload.clear();
+                    continued = true;
+                }
+                segment_table.push(0);
+            }
+        }
+
+        let flags = header_type | if continued { 0x01 } else { 0x00 };
+        output.extend(self.write_page(&segment_table, &payload, granule_position, flags));
+
+        output
+    }
+
+    fn write_page(&mut self, segment_table: &[u8], payload: &[u8], granule_position: u64, header_type: u8) -> Vec<u8> {
+        let mut page = Vec::with_capacity(27 + segment_table.len() + payload.len());
+        page.extend_from_slice(b"OggS");
+        page.push(0); // version
+        page.push(header_type);
+        page.extend_from_slice(&(granule_position as i64).to_le_bytes());
+        page.extend_from_slice(&self.serial.to_le_bytes());
+        page.extend_from_slice(&self.sequence.to_le_bytes());
+        page.extend_from_slice(&[0u8; 4]); // checksum placeholder
+        page.push(segment_table.len() as u8);
+        page.extend_from_slice(segment_table);
+        page.extend_from_slice(payload);
+
+        let checksum = ogg_crc32(&page);
+        page[22..26].copy_from_slice(&checksum.to_le_bytes());
+
+        self.sequence += 1;
+        page
+    }
+}
+
+fn build_opus_tags() -> Vec<u8> {
+    let vendor = b"webm_iterable";
+    let mut data = Vec::new();
+    data.extend_from_slice(b"OpusTags");
+    data.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    data.extend_from_slice(vendor);
+    data.extend_from_slice(&0u32.to_le_bytes()); // no user comments
+    data
+}
+
+fn ogg_crc32(data: &[u8]) -> u32 {
+    const POLYNOMIAL: u32 = 0x04c1_1db7;
+
+    let mut crc: u32 = 0;
+    for byte in data {
+        crc ^= (*byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ POLYNOMIAL
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ebml_iterable::tags::TagData;
+
+    use super::*;
+
+    fn opus_track() -> TrackEntry {
+        TrackEntry {
+            number: 1,
+            uid: 1,
+            codec_id: String::from(OPUS_CODEC_ID),
+            codec_private: Some(vec![b'O', b'p', b'u', b's', b'H', b'e', b'a', b'd', 1, 2]),
+            language: None,
+            default_duration: None,
+            audio: None,
+            video: None,
+        }
+    }
+
+    #[test]
+    fn rejects_non_opus_tracks() {
+        let mut track = opus_track();
+        track.codec_id = String::from("A_VORBIS");
+
+        assert!(OggOpusRemuxer::new(track, 1).is_none());
+    }
+
+    #[test]
+    fn header_pages_start_with_ogg_capture_pattern() {
+        let mut remuxer = OggOpusRemuxer::new(opus_track(), 1).unwrap();
+        let headers = remuxer.write_headers().unwrap();
+
+        assert_eq!(b"OggS", &headers[0..4]);
+    }
+
+    #[test]
+    fn granule_position_accounts_for_pre_skip_and_block_duration() {
+        let mut remuxer = OggOpusRemuxer::new(opus_track(), 1).unwrap();
+        remuxer.write_headers().unwrap();
+
+        // track 1, timecode 0, no lacing, one Opus frame
+        let block_content = vec![0x81, 0x00, 0x00, 0x00, 0xAB, 0xCD];
+        let block = Block::try_from(TagData::Binary(block_content)).unwrap();
+
+        let page = remuxer.write_block(&block, 0, 1_000_000).unwrap();
+        let granule_position = i64::from_le_bytes(page[6..14].try_into().unwrap()) as u64;
+
+        assert_eq!(OPUS_SAMPLES_PER_FRAME + OPUS_PRE_SKIP, granule_position);
+    }
+
+    #[test]
+    fn splits_oversized_block_into_continuation_pages() {
+        let mut remuxer = OggOpusRemuxer::new(opus_track(), 1).unwrap();
+        remuxer.write_headers().unwrap();
+
+        // track 1, timecode 0, no lacing, a single frame far larger than 255*255 bytes
+        let mut block_content = vec![0x81, 0x00, 0x00, 0x00];
+        block_content.extend(std::iter::repeat(0xAB).take(70_000));
+        let block = Block::try_from(TagData::Binary(block_content)).unwrap();
+
+        let pages = remuxer.write_block(&block, 0, 1_000_000).unwrap();
+        let page_count = pages.windows(4).filter(|window| *window == b"OggS").count();
+
+        assert!(page_count > 1);
+    }
+
+    #[test]
+    fn terminates_a_packet_whose_length_is_an_exact_multiple_of_255() {
+        let mut remuxer = OggOpusRemuxer::new(opus_track(), 1).unwrap();
+        remuxer.write_headers().unwrap();
+
+        // track 1, timecode 0, no lacing, a single frame exactly 510 bytes (2 * 255) long
+        let mut block_content = vec![0x81, 0x00, 0x00, 0x00];
+        block_content.extend(std::iter::repeat(0xAB).take(510));
+        let block = Block::try_from(TagData::Binary(block_content)).unwrap();
+
+        let page = remuxer.write_block(&block, 0, 1_000_000).unwrap();
+        let segment_count = page[26] as usize;
+        let segment_table = &page[27..27 + segment_count];
+
+        assert_eq!(3, segment_count);
+        assert_eq!(&[255, 255, 0], segment_table);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::matroska_spec::Block;
+
+///
+/// A [`Block`]/[`SimpleBlock`](crate::matroska_spec::SimpleBlock) paired with the (possibly rewritten) Cluster `Timecode` it belongs to.
+///
+/// This is returned by [`TimecodeFixer::process`] since fixing up a block's absolute position may require adjusting
+/// both the block itself and the enclosing cluster's timecode.
+///
+pub struct FixedBlock {
+    pub block: Block,
+    pub cluster_timecode: u64,
+}
+
+///
+/// Rewrites Cluster `Timecode` values so that several independently-recorded WebM segments can be glued
+/// together into a single, monotonically-increasing stream.
+///
+/// Each independently-recorded segment typically restarts its `Timecode` values near zero.  `TimecodeFixer`
+/// tracks the last absolute timestamp it has emitted and, whenever it detects a backward jump (a new segment
+/// starting), accumulates an offset so the stitched-together stream keeps increasing.  The offset is applied
+/// to the Cluster `Timecode` only - a block's own timecode is already relative to its enclosing cluster, so
+/// shifting the cluster forward moves the block with it without needing to touch [`Block::value`].
+///
+/// ## Example
+///
+/// ```
+/// use webm_iterable::timecode_fixer::TimecodeFixer;
+///
+/// let mut fixer = TimecodeFixer::new();
+/// let fixed_timecode = fixer.process_cluster_timecode(0);
+/// assert_eq!(0, fixed_timecode);
+/// ```
+///
+pub struct TimecodeFixer {
+    last_timestamp: Option<i64>,
+    offset: i64,
+}
+
+impl TimecodeFixer {
+    pub fn new() -> Self {
+        TimecodeFixer {
+            last_timestamp: None,
+            offset: 0,
+        }
+    }
+
+    ///
+    /// Rewrites a Cluster `Timecode` (in the segment's native tick units), applying the currently accumulated
+    /// offset and growing that offset if this timecode would otherwise jump backwards relative to the last one
+    /// this fixer has seen.
+    ///
+    pub fn process_cluster_timecode(&mut self, cluster_timecode: u64) -> u64 {
+        let incoming = cluster_timecode as i64 + self.offset;
+
+        if let Some(last_timestamp) = self.last_timestamp {
+            if incoming < last_timestamp {
+                self.offset += (last_timestamp - incoming) + 1;
+            }
+        }
+
+        let fixed = cluster_timecode as i64 + self.offset;
+        self.last_timestamp = Some(fixed);
+        fixed as u64
+    }
+
+    ///
+    /// Rewrites a block's enclosing Cluster `Timecode` to stay monotonically increasing across segment
+    /// boundaries.  The block itself is returned unmodified - its relative timecode is still correct
+    /// against the rewritten cluster, since the whole cluster (and everything in it) moved together.
+    ///
+    pub fn process(&mut self, block: Block, original_cluster_timecode: u64) -> FixedBlock {
+        let fixed_cluster_timecode = self.process_cluster_timecode(original_cluster_timecode);
+
+        FixedBlock {
+            block,
+            cluster_timecode: fixed_cluster_timecode,
+        }
+    }
+}
+
+impl Default for TimecodeFixer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ebml_iterable::tags::TagData;
+
+    use super::TimecodeFixer;
+    use crate::matroska_spec::Block;
+
+    #[test]
+    fn monotonic_timecodes_pass_through_unchanged() {
+        let mut fixer = TimecodeFixer::new();
+
+        assert_eq!(0, fixer.process_cluster_timecode(0));
+        assert_eq!(1000, fixer.process_cluster_timecode(1000));
+        assert_eq!(2000, fixer.process_cluster_timecode(2000));
+    }
+
+    #[test]
+    fn backward_jump_is_offset_forward_by_exactly_one_tick() {
+        let mut fixer = TimecodeFixer::new();
+
+        assert_eq!(5000, fixer.process_cluster_timecode(5000));
+        // a new segment starts and its timecodes restart near zero
+        let fixed = fixer.process_cluster_timecode(0);
+        assert_eq!(5001, fixed);
+
+        let next_fixed = fixer.process_cluster_timecode(1000);
+        assert_eq!(fixed + 1000, next_fixed);
+    }
+
+    #[test]
+    fn process_keeps_block_absolute_timestamp_monotonic_across_segments() {
+        let block_content = vec![0x81, 0x00, 0x01, 0x00, 0x01, 0x02, 0x03];
+        let block = Block::try_from(TagData::Binary(block_content.clone())).unwrap();
+
+        let mut fixer = TimecodeFixer::new();
+        let first = fixer.process(block, 5000);
+        let first_absolute = first.block.absolute_timestamp_ticks(first.cluster_timecode);
+
+        // a new segment starts, restarting its cluster timecode near zero
+        let block = Block::try_from(TagData::Binary(block_content)).unwrap();
+        let second = fixer.process(block, 0);
+        let second_absolute = second.block.absolute_timestamp_ticks(second.cluster_timecode);
+
+        assert!(second_absolute > first_absolute);
+    }
+}